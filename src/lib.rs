@@ -29,13 +29,20 @@
 //! #[macro_use]
 //! extern crate xplane_plugin;
 //! use xplane_plugin::*;
+//! use std::fmt;
 //! struct TestPlugin;
-//! impl Plugin for TestPlugin {
-//!     fn start() -> Option<Self> {
-//!         Some(TestPlugin)
+//! #[derive(Debug)]
+//! struct TestError;
+//! impl fmt::Display for TestError {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         write!(f, "test error")
 //!     }
-//!     fn enable(&mut self) {
-//!
+//! }
+//! impl std::error::Error for TestError {}
+//! impl Plugin for TestPlugin {
+//!     type Error = TestError;
+//!     fn start() -> Result<Self, Self::Error> {
+//!         Ok(TestPlugin)
 //!     }
 //!     fn disable(&mut self) {
 //!
@@ -44,11 +51,15 @@
 //!     fn stop(&mut self) {
 //!
 //!     }
-//!     fn info<'a, 'b, 'c>(&self) -> PluginInfo<'a, 'b, 'c> {
+//!     fn info(&self) -> PluginInfo {
 //!         PluginInfo {
-//!             name: "Test Plugin",
-//!             signature: "org.samcrow.rustplugin.test",
-//!             description: "A plugin written in Rust",
+//!             name: "Test Plugin".to_string(),
+//!             signature: "org.samcrow.rustplugin.test".to_string(),
+//!             description: "A plugin written in Rust".to_string(),
+//!             version: "1.0.0".to_string(),
+//!             license: "MIT".to_string(),
+//!             package: None,
+//!             url: None,
 //!         }
 //!     }
 //! }
@@ -57,31 +68,156 @@
 //! ```
 //!
 
+extern crate libc;
+
+pub mod management;
+
 /// Stores information about a plugin that is provided to X-Plane
-pub struct PluginInfo<'a, 'b, 'c> {
+pub struct PluginInfo {
     /// The plugin name
-    pub name: &'a str,
+    pub name: String,
     /// The plugin's signature, in reverse DNS format
-    pub signature: &'b str,
+    pub signature: String,
     /// A description of the plugin
-    pub description: &'c str,
+    pub description: String,
+    /// The plugin's version
+    pub version: String,
+    /// The license that the plugin is distributed under
+    pub license: String,
+    /// The package or crate that the plugin was built from, if applicable
+    pub package: Option<String>,
+    /// A URL where users can find more information about the plugin
+    pub url: Option<String>,
+}
+
+/// A message sent by X-Plane or another plugin
+///
+/// X-Plane broadcasts several well-known messages (using the `XPLM_MSG_*` constants) to signal
+/// events such as aircraft or scenery being loaded. Plugins can also send each other arbitrary
+/// integer messages, which are represented by the `Custom` variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XPlaneMessage {
+    /// The user's plane has finished loading (`XPLM_MSG_PLANE_LOADED`)
+    PlaneLoaded,
+    /// The user's plane is about to be unloaded (`XPLM_MSG_PLANE_UNLOADED`)
+    PlaneUnloaded,
+    /// The user's plane crashed (`XPLM_MSG_PLANE_CRASHED`)
+    PlaneCrashed,
+    /// The user's plane was repositioned at an airport (`XPLM_MSG_PLANE_LOADED`'s airport variant,
+    /// `XPLM_MSG_AIRPORT_LOADED`)
+    AirportLoaded,
+    /// New scenery has been loaded (`XPLM_MSG_SCENERY_LOADED`)
+    SceneryLoaded,
+    /// The user has changed the number of X-Plane aircraft models (`XPLM_MSG_AIRPLANE_COUNT_CHANGED`)
+    AirplaneCountChanged,
+    /// X-Plane is about to write its preferences to disk (`XPLM_MSG_WILL_WRITE_PREFS`)
+    WillWritePrefs,
+    /// The livery of an aircraft was loaded (`XPLM_MSG_LIVERY_LOADED`)
+    LiveryLoaded,
+    /// The user has entered virtual reality mode (`XPLM_MSG_ENTERED_VR`)
+    EnteredVr,
+    /// The user is about to leave virtual reality mode (`XPLM_MSG_EXITING_VR`)
+    ExitingVr,
+    /// X-Plane is about to release plane-related plugin resources (`XPLM_MSG_RELEASE_PLANES`)
+    ReleasePlanes,
+    /// An FMOD sound bank has been loaded (`XPLM_MSG_FMOD_BANK_LOADED`)
+    FmodBankLoaded,
+    /// One or more new datarefs have been registered (`XPLM_MSG_DATAREFS_ADDED`)
+    DatarefsAdded,
+    /// An FMOD sound bank is about to be unloaded (`XPLM_MSG_FMOD_BANK_UNLOADING`)
+    FmodBankUnloading,
+    /// A message not recognized as one of the well-known X-Plane messages, carrying the raw
+    /// message number. Used for custom messages that plugins send each other.
+    Custom(i32),
+}
+
+impl XPlaneMessage {
+    /// Converts a raw X-Plane message number into an `XPlaneMessage`
+    pub fn from_raw(message: i32) -> XPlaneMessage {
+        match message {
+            101 => XPlaneMessage::PlaneCrashed,
+            102 => XPlaneMessage::PlaneLoaded,
+            103 => XPlaneMessage::AirportLoaded,
+            104 => XPlaneMessage::SceneryLoaded,
+            105 => XPlaneMessage::AirplaneCountChanged,
+            106 => XPlaneMessage::PlaneUnloaded,
+            107 => XPlaneMessage::WillWritePrefs,
+            108 => XPlaneMessage::LiveryLoaded,
+            109 => XPlaneMessage::EnteredVr,
+            110 => XPlaneMessage::ExitingVr,
+            111 => XPlaneMessage::ReleasePlanes,
+            112 => XPlaneMessage::FmodBankLoaded,
+            113 => XPlaneMessage::DatarefsAdded,
+            114 => XPlaneMessage::FmodBankUnloading,
+            other => XPlaneMessage::Custom(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod xplane_message_tests {
+    use super::XPlaneMessage;
+
+    #[test]
+    fn from_raw_maps_known_codes() {
+        assert_eq!(XPlaneMessage::from_raw(101), XPlaneMessage::PlaneCrashed);
+        assert_eq!(XPlaneMessage::from_raw(102), XPlaneMessage::PlaneLoaded);
+        assert_eq!(XPlaneMessage::from_raw(103), XPlaneMessage::AirportLoaded);
+        assert_eq!(XPlaneMessage::from_raw(104), XPlaneMessage::SceneryLoaded);
+        assert_eq!(XPlaneMessage::from_raw(105), XPlaneMessage::AirplaneCountChanged);
+        assert_eq!(XPlaneMessage::from_raw(106), XPlaneMessage::PlaneUnloaded);
+        assert_eq!(XPlaneMessage::from_raw(107), XPlaneMessage::WillWritePrefs);
+        assert_eq!(XPlaneMessage::from_raw(108), XPlaneMessage::LiveryLoaded);
+        assert_eq!(XPlaneMessage::from_raw(109), XPlaneMessage::EnteredVr);
+        assert_eq!(XPlaneMessage::from_raw(110), XPlaneMessage::ExitingVr);
+        assert_eq!(XPlaneMessage::from_raw(111), XPlaneMessage::ReleasePlanes);
+        assert_eq!(XPlaneMessage::from_raw(112), XPlaneMessage::FmodBankLoaded);
+        assert_eq!(XPlaneMessage::from_raw(113), XPlaneMessage::DatarefsAdded);
+        assert_eq!(XPlaneMessage::from_raw(114), XPlaneMessage::FmodBankUnloading);
+    }
+
+    #[test]
+    fn from_raw_falls_back_to_custom() {
+        assert_eq!(XPlaneMessage::from_raw(9999), XPlaneMessage::Custom(9999));
+    }
 }
 
 /// The trait that all plugins should implement
 pub trait Plugin : Sized {
+    /// The error type returned when this plugin fails to start or to enable
+    type Error: std::error::Error;
+
     /// Called when X-Plane loads this plugin
     /// On success, returns a plugin object
-    fn start() -> Option<Self>;
+    fn start() -> Result<Self, Self::Error>;
     /// Called when the plugin is enabled
-    fn enable(&mut self);
+    ///
+    /// If this returns an error, X-Plane leaves the plugin disabled.
+    /// The default implementation succeeds without doing anything.
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
     /// Called when the plugin is disabled
     fn disable(&mut self);
 
     /// Returns information on this plugin
-    fn info<'a, 'b, 'c>(&self) -> PluginInfo<'a, 'b, 'c>;
+    fn info(&self) -> PluginInfo;
 
     // Called when the plugin is stopped
     fn stop(&mut self);
+
+    /// Called when a message is sent to this plugin, either by X-Plane or by another plugin
+    ///
+    /// `from` is the ID of the plugin that sent the message, or `XPLM_PLUGIN_XPLANE` if X-Plane
+    /// sent it. `message` identifies the message; use `XPlaneMessage::from_raw` to interpret it.
+    /// `param` carries any data associated with the message, if applicable; its meaning depends
+    /// on the message.
+    ///
+    /// The default implementation does nothing.
+    #[allow(unused_variables)]
+    fn receive_message(&mut self, from: i32, message: i32, param: *mut libc::c_void) {
+        // Do nothing by default
+    }
 }
 
 /// Creates an X-Plane plugin
@@ -105,10 +241,10 @@ macro_rules! xplane_plugin {
             outDescription: *mut libc::c_char) -> libc::c_int
         {
             // Create the plugin, temporarily, on the stack
-            let plugin_option = PluginType::start();
+            let plugin_result = PluginType::start();
 
-            match plugin_option {
-                Some(plugin) => {
+            match plugin_result {
+                Ok(plugin) => {
                     // Allocate storage
                     PLUGIN = Box::into_raw(Box::new(plugin));
 
@@ -130,8 +266,13 @@ macro_rules! xplane_plugin {
                     // Success
                     1
                 },
-                None => {
-                    // Return failure
+                Err(error) => {
+                    // Report the error and return failure
+                    let message = format!("{}", error);
+                    match ffi::CString::new(message).ok() {
+                        Some(message) => libc::strcpy(outDescription, message.as_ptr()),
+                        None => libc::strcpy(outDescription, b"<invalid>".as_ptr() as *const libc::c_char),
+                    };
                     0
                 },
             }
@@ -149,8 +290,11 @@ macro_rules! xplane_plugin {
 
         #[allow(non_snake_case)]
         #[no_mangle]
-        pub unsafe extern "C" fn XPluginEnable() {
-            (*PLUGIN).enable();
+        pub unsafe extern "C" fn XPluginEnable() -> libc::c_int {
+            match (*PLUGIN).enable() {
+                Ok(()) => 1,
+                Err(_) => 0,
+            }
         }
 
         #[allow(non_snake_case)]
@@ -160,12 +304,11 @@ macro_rules! xplane_plugin {
         }
 
         #[allow(non_snake_case)]
-        #[allow(unused_variables)]
         #[no_mangle]
         pub unsafe extern "C" fn XPluginReceiveMessage(inFrom: libc::c_int, inMessage: libc::c_int,
             inParam: *mut libc::c_void)
         {
-            // Nothing
+            (*PLUGIN).receive_message(inFrom, inMessage, inParam);
         }
     )
 }
@@ -174,13 +317,22 @@ macro_rules! xplane_plugin {
 #[cfg(test)]
 pub mod test {
     use super::*;
-    struct TestPlugin;
-    impl Plugin for TestPlugin {
-        fn start() -> Option<Self> {
-            Some(TestPlugin)
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct TestError;
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "test error")
         }
-        fn enable(&mut self) {
+    }
+    impl std::error::Error for TestError {}
 
+    struct TestPlugin;
+    impl Plugin for TestPlugin {
+        type Error = TestError;
+        fn start() -> Result<Self, Self::Error> {
+            Ok(TestPlugin)
         }
         fn disable(&mut self) {
 
@@ -189,11 +341,15 @@ pub mod test {
         fn stop(&mut self) {
 
         }
-        fn info<'a, 'b, 'c>(&self) -> PluginInfo<'a, 'b, 'c> {
+        fn info(&self) -> PluginInfo {
             PluginInfo {
-                name: "Test Plugin",
-                signature: "org.samcrow.rustplugin.test",
-                description: "A plugin written in Rust",
+                name: "Test Plugin".to_string(),
+                signature: "org.samcrow.rustplugin.test".to_string(),
+                description: "A plugin written in Rust".to_string(),
+                version: "1.0.0".to_string(),
+                license: "MIT".to_string(),
+                package: None,
+                url: None,
             }
         }
     }
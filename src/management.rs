@@ -0,0 +1,197 @@
+// Copyright (c) 2015 rust-x-plane-plugin developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Safe wrappers over the XPLM plugin-management API
+//!
+//! These functions let a plugin find other loaded plugins, inspect them, enable or disable them,
+//! and send them messages. This pairs with `Plugin::receive_message` to let plugins coordinate
+//! with each other.
+
+use libc::{c_char, c_int, c_void};
+use std::ffi::{CStr, CString};
+
+/// Identifies a loaded plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PluginId(i32);
+
+/// The length of the buffers that XPLMGetPluginInfo fills in
+const INFO_BUFFER_SIZE: usize = 256;
+
+extern "C" {
+    fn XPLMGetMyID() -> c_int;
+    fn XPLMCountPlugins() -> c_int;
+    fn XPLMGetNthPlugin(index: c_int) -> c_int;
+    fn XPLMFindPluginBySignature(signature: *const c_char) -> c_int;
+    fn XPLMGetPluginInfo(plugin: c_int, outName: *mut c_char, outFilePath: *mut c_char,
+        outSignature: *mut c_char, outDescription: *mut c_char);
+    fn XPLMIsPluginEnabled(plugin: c_int) -> c_int;
+    fn XPLMEnablePlugin(plugin: c_int) -> c_int;
+    fn XPLMDisablePlugin(plugin: c_int);
+    fn XPLMSendMessageToPlugin(plugin: c_int, message: c_int, param: *mut c_void);
+}
+
+/// Information about a loaded plugin, as reported by X-Plane
+pub struct PluginInfo {
+    /// The plugin's name
+    pub name: String,
+    /// The path to the file that the plugin was loaded from
+    pub file_path: String,
+    /// The plugin's signature, in reverse DNS format
+    pub signature: String,
+    /// A description of the plugin
+    pub description: String,
+    /// The plugin's version, if known
+    ///
+    /// X-Plane does not report this for plugins in general, so it is only filled in for this
+    /// plugin via [`PluginInfo::with_metadata`].
+    pub version: Option<String>,
+    /// The license that the plugin is distributed under, if known
+    ///
+    /// X-Plane does not report this for plugins in general, so it is only filled in for this
+    /// plugin via [`PluginInfo::with_metadata`].
+    pub license: Option<String>,
+    /// The package or crate that the plugin was built from, if known
+    pub package: Option<String>,
+    /// A URL where users can find more information about the plugin, if known
+    pub url: Option<String>,
+}
+
+impl PluginInfo {
+    /// Fills in the `version`, `license`, `package`, and `url` fields from a plugin's own
+    /// [`crate::PluginInfo`]
+    ///
+    /// Used by [`this_plugin_info`] to merge this plugin's own metadata into the result of
+    /// [`info`], which X-Plane cannot otherwise supply for plugins in general.
+    pub fn with_metadata(mut self, own_info: &crate::PluginInfo) -> Self {
+        self.version = Some(own_info.version.clone());
+        self.license = Some(own_info.license.clone());
+        self.package = own_info.package.clone();
+        self.url = own_info.url.clone();
+        self
+    }
+}
+
+/// Returns the ID of this plugin
+pub fn this_plugin() -> PluginId {
+    unsafe { PluginId(XPLMGetMyID()) }
+}
+
+/// Finds a loaded plugin by its signature, returning `None` if no loaded plugin has that
+/// signature
+pub fn find_by_signature(signature: &str) -> Option<PluginId> {
+    let signature = CString::new(signature).ok()?;
+    let id = unsafe { XPLMFindPluginBySignature(signature.as_ptr()) };
+    if id == -1 {
+        None
+    } else {
+        Some(PluginId(id))
+    }
+}
+
+/// Returns an iterator over the IDs of all plugins that are currently loaded, including
+/// disabled plugins and this plugin
+pub fn enumerate() -> impl Iterator<Item = PluginId> {
+    let count = unsafe { XPLMCountPlugins() };
+    (0..count).map(|index| PluginId(unsafe { XPLMGetNthPlugin(index) }))
+}
+
+/// Returns information about the specified plugin
+pub fn info(plugin: PluginId) -> PluginInfo {
+    let mut name = [0 as c_char; INFO_BUFFER_SIZE];
+    let mut file_path = [0 as c_char; INFO_BUFFER_SIZE];
+    let mut signature = [0 as c_char; INFO_BUFFER_SIZE];
+    let mut description = [0 as c_char; INFO_BUFFER_SIZE];
+    unsafe {
+        XPLMGetPluginInfo(plugin.0, name.as_mut_ptr(), file_path.as_mut_ptr(),
+            signature.as_mut_ptr(), description.as_mut_ptr());
+        PluginInfo {
+            name: CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned(),
+            file_path: CStr::from_ptr(file_path.as_ptr()).to_string_lossy().into_owned(),
+            signature: CStr::from_ptr(signature.as_ptr()).to_string_lossy().into_owned(),
+            description: CStr::from_ptr(description.as_ptr()).to_string_lossy().into_owned(),
+            version: None,
+            license: None,
+            package: None,
+            url: None,
+        }
+    }
+}
+
+/// Returns information about this plugin, including the `version`, `license`, `package`, and
+/// `url` metadata from its own [`crate::PluginInfo`]
+///
+/// `own_info` should be the value returned by this plugin's `Plugin::info()`. X-Plane's
+/// plugin-management API does not expose that metadata for plugins in general, so [`info`]
+/// alone cannot fill it in.
+pub fn this_plugin_info(own_info: &crate::PluginInfo) -> PluginInfo {
+    info(this_plugin()).with_metadata(own_info)
+}
+
+/// Returns true if the specified plugin is enabled
+pub fn is_enabled(plugin: PluginId) -> bool {
+    unsafe { XPLMIsPluginEnabled(plugin.0) != 0 }
+}
+
+/// Enables the specified plugin, returning true if it was enabled successfully
+pub fn enable(plugin: PluginId) -> bool {
+    unsafe { XPLMEnablePlugin(plugin.0) != 0 }
+}
+
+/// Disables the specified plugin
+pub fn disable(plugin: PluginId) {
+    unsafe { XPLMDisablePlugin(plugin.0) }
+}
+
+/// Sends a message to the specified plugin
+///
+/// `param` carries any data associated with the message; its meaning depends on the message and
+/// must be agreed on by the sender and the receiver.
+///
+/// # Safety
+///
+/// The caller must ensure that `param` is valid for however the receiving plugin interprets
+/// the message.
+pub unsafe fn send_message(target: PluginId, message: i32, param: *mut c_void) {
+    XPLMSendMessageToPlugin(target.0, message, param)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PluginInfo;
+
+    #[test]
+    fn with_metadata_merges_own_plugin_info() {
+        let discovered = PluginInfo {
+            name: "Test Plugin".to_string(),
+            file_path: "/path/to/plugin.xpl".to_string(),
+            signature: "org.samcrow.rustplugin.test".to_string(),
+            description: "A plugin written in Rust".to_string(),
+            version: None,
+            license: None,
+            package: None,
+            url: None,
+        };
+        let own_info = crate::PluginInfo {
+            name: "Test Plugin".to_string(),
+            signature: "org.samcrow.rustplugin.test".to_string(),
+            description: "A plugin written in Rust".to_string(),
+            version: "1.0.0".to_string(),
+            license: "MIT".to_string(),
+            package: Some("xplane_plugin".to_string()),
+            url: None,
+        };
+
+        let merged = discovered.with_metadata(&own_info);
+
+        assert_eq!(merged.version.as_deref(), Some("1.0.0"));
+        assert_eq!(merged.license.as_deref(), Some("MIT"));
+        assert_eq!(merged.package.as_deref(), Some("xplane_plugin"));
+        assert_eq!(merged.url, None);
+    }
+}